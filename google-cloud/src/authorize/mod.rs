@@ -11,12 +11,32 @@ use async_trait::async_trait;
 
 use crate::error::AuthError;
 
-#[allow(unused)]
-pub(crate) const TLS_CERTS: &[u8] = include_bytes!("../../roots.pem");
-
 const AUTH_ENDPOINT: &str = "https://oauth2.googleapis.com/token";
 const META_ENDPOINT: &str = "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
 
+/// Build the HTTPS connector shared by every [`TokenProvider`] that talks to Google over hyper.
+///
+/// With the `rustls-tls` feature, the connector is pinned to the bundled Mozilla root store
+/// instead of the platform's, so the crate builds and runs cleanly on musl/static targets
+/// without OpenSSL.
+#[cfg(feature = "rustls-tls")]
+fn https_connector() -> HttpsConnector<HttpConnector> {
+    hyper_rustls::HttpsConnectorBuilder::new()
+        .with_webpki_roots()
+        .https_only()
+        .enable_http1()
+        .build()
+}
+
+#[cfg(not(feature = "rustls-tls"))]
+fn https_connector() -> HttpsConnector<HttpConnector> {
+    hyper_rustls::HttpsConnectorBuilder::new()
+        .with_native_roots()
+        .https_only()
+        .enable_http1()
+        .build()
+}
+
 /// Represents application credentials for accessing Google Cloud Platform services.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -34,6 +54,38 @@ pub struct ApplicationCredentials {
     pub client_x509_cert_url: String,
 }
 
+/// Represents the `authorized_user` credentials written by
+/// `gcloud auth application-default login`, as opposed to a service account key.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthorizedUserCredentials {
+    pub client_id: String,
+    pub client_secret: String,
+    pub refresh_token: String,
+}
+
+/// Represents an `external_account` credentials file (Workload Identity Federation), letting
+/// the crate run outside GCP without a long-lived service-account key.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalAccountCredentials {
+    pub audience: String,
+    pub subject_token_type: String,
+    pub token_url: String,
+    pub credential_source: CredentialSource,
+    #[serde(default)]
+    pub service_account_impersonation_url: Option<String>,
+}
+
+/// Where to read the external subject token from, before it's exchanged for a GCP token.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CredentialSource {
+    File { file: String },
+    Url { url: String },
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub(crate) enum TokenValue {
     Bearer(String),
@@ -68,7 +120,7 @@ pub(crate) struct MetadataManager {
 impl MetadataManager {
     pub(crate) fn new(scopes: &[&str]) -> MetadataManager {
         MetadataManager {
-            client: Client::builder().build::<_, hyper::Body>(HttpsConnector::new()),
+            client: Client::builder().build::<_, hyper::Body>(https_connector()),
             scopes: scopes.join(" "),
             current_token: None,
         }
@@ -85,9 +137,10 @@ impl TokenProvider for MetadataManager {
             }
         }
 
+        let uri = format!("{}?scopes={}", META_ENDPOINT, self.scopes.replace(' ', ","));
         let req = hyper::Request::builder()
             .method("GET")
-            .uri(META_ENDPOINT)
+            .uri(uri)
             .header("Metadata-Flavor", "Google")
             .body(Body::empty())?;
 
@@ -126,7 +179,7 @@ impl TokenManager {
     pub(crate) fn new(creds: ApplicationCredentials, scopes: &[&str]) -> TokenManager {
         TokenManager {
             creds,
-            client: Client::builder().build::<_, hyper::Body>(HttpsConnector::new()),
+            client: Client::builder().build::<_, hyper::Body>(https_connector()),
             scopes: scopes.join(" "),
             current_token: None,
         }
@@ -153,12 +206,7 @@ impl TokenProvider for TokenManager {
                     "exp": expiry.timestamp(),
                     "iat": current_time.timestamp(),
                 });
-                let token = jwt::encode(
-                    header,
-                    &self.creds.private_key.as_str(),
-                    &payload,
-                    jwt::Algorithm::RS256,
-                )?;
+                let token = sign_jwt(&header, &payload, self.creds.private_key.as_str())?;
                 let form = format!(
                     "grant_type=urn:ietf:params:oauth:grant-type:jwt-bearer&assertion={}",
                     token.as_str()
@@ -185,3 +233,207 @@ impl TokenProvider for TokenManager {
         }
     }
 }
+
+/// Exchanges a long-lived OAuth2 refresh token (as found in the gcloud `authorized_user`
+/// credentials file) for short-lived access tokens.
+#[derive(Debug, Clone)]
+pub(crate) struct UserTokenManager {
+    client: Client<HttpsConnector<HttpConnector>>,
+    creds: AuthorizedUserCredentials,
+    current_token: Option<Token>,
+}
+
+impl UserTokenManager {
+    pub(crate) fn new(creds: AuthorizedUserCredentials) -> UserTokenManager {
+        UserTokenManager {
+            creds,
+            client: Client::builder().build::<_, hyper::Body>(https_connector()),
+            current_token: None,
+        }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for UserTokenManager {
+    async fn token(&mut self) -> Result<String, AuthError> {
+        let current_time = chrono::Utc::now();
+        if let Some(ref token) = self.current_token {
+            if token.expiry >= current_time {
+                return Ok(token.value.to_string());
+            }
+        }
+
+        let form = format!(
+            "client_id={}&client_secret={}&refresh_token={}&grant_type=refresh_token",
+            self.creds.client_id.as_str(),
+            self.creds.client_secret.as_str(),
+            self.creds.refresh_token.as_str(),
+        );
+
+        let req = hyper::Request::builder()
+            .method("POST")
+            .uri(AUTH_ENDPOINT)
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(Body::from(form))?;
+
+        let data = hyper::body::to_bytes(self.client.request(req).await?.into_body()).await?;
+        let ar: AuthResponse = json::from_slice(&data)?;
+
+        let token = Token {
+            value: TokenValue::Bearer(ar.access_token),
+            expiry: chrono::Utc::now() + chrono::Duration::seconds(ar.expires_in),
+        };
+
+        let token_str = token.value.to_string();
+        self.current_token = Some(token);
+        Ok(token_str)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StsResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImpersonationResponse {
+    access_token: String,
+    expire_time: DateTime<Utc>,
+}
+
+/// Exchanges a federated (OIDC/SAML) subject token for a short-lived GCP access token via STS
+/// token exchange, optionally impersonating a service account along the way.
+#[derive(Debug, Clone)]
+pub(crate) struct ExternalAccountManager {
+    client: Client<HttpsConnector<HttpConnector>>,
+    scopes: String,
+    creds: ExternalAccountCredentials,
+    current_token: Option<Token>,
+}
+
+impl ExternalAccountManager {
+    pub(crate) fn new(creds: ExternalAccountCredentials, scopes: &[&str]) -> ExternalAccountManager {
+        ExternalAccountManager {
+            creds,
+            client: Client::builder().build::<_, hyper::Body>(https_connector()),
+            scopes: scopes.join(" "),
+            current_token: None,
+        }
+    }
+
+    /// Fetch the subject token from wherever `credential_source` points.
+    async fn subject_token(&self) -> Result<String, AuthError> {
+        let token = match &self.creds.credential_source {
+            CredentialSource::File { file } => std::fs::read_to_string(file)?,
+            CredentialSource::Url { url } => {
+                let req = hyper::Request::builder()
+                    .method("GET")
+                    .uri(url.as_str())
+                    .header("Metadata", "True")
+                    .body(Body::empty())?;
+
+                let data = hyper::body::to_bytes(self.client.request(req).await?.into_body()).await?;
+                String::from_utf8_lossy(&data).into_owned()
+            }
+        };
+
+        Ok(token.trim().to_string())
+    }
+
+    /// Exchange a federated access token for one scoped to the impersonated service account, via
+    /// the IAM `generateAccessToken` endpoint. Returns the impersonated token along with its own
+    /// expiry, which is independent of (and commonly longer-lived than) the federated token's.
+    async fn impersonate(
+        &self,
+        url: &str,
+        federated_token: &str,
+    ) -> Result<(String, DateTime<Utc>), AuthError> {
+        let body = json!({ "scope": self.scopes.split(' ').collect::<Vec<_>>() });
+
+        let req = hyper::Request::builder()
+            .method("POST")
+            .uri(url)
+            .header("Authorization", format!("Bearer {}", federated_token))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))?;
+
+        let data = hyper::body::to_bytes(self.client.request(req).await?.into_body()).await?;
+        let response: ImpersonationResponse = json::from_slice(&data)?;
+
+        Ok((response.access_token, response.expire_time))
+    }
+}
+
+#[async_trait]
+impl TokenProvider for ExternalAccountManager {
+    async fn token(&mut self) -> Result<String, AuthError> {
+        let current_time = chrono::Utc::now();
+        if let Some(ref token) = self.current_token {
+            if token.expiry >= current_time {
+                return Ok(token.value.to_string());
+            }
+        }
+
+        let subject_token = self.subject_token().await?;
+
+        let form = format!(
+            "grant_type=urn:ietf:params:oauth:grant-type:token-exchange&audience={}&scope={}&requested_token_type=urn:ietf:params:oauth:token-type:access_token&subject_token_type={}&subject_token={}",
+            self.creds.audience.as_str(),
+            self.scopes.as_str(),
+            self.creds.subject_token_type.as_str(),
+            subject_token.as_str(),
+        );
+
+        let req = hyper::Request::builder()
+            .method("POST")
+            .uri(self.creds.token_url.as_str())
+            .header("Content-Type", "application/x-www-form-urlencoded")
+            .body(Body::from(form))?;
+
+        let data = hyper::body::to_bytes(self.client.request(req).await?.into_body()).await?;
+        let sts: StsResponse = json::from_slice(&data)?;
+
+        let (access_token, expiry) = match &self.creds.service_account_impersonation_url {
+            Some(url) => self.impersonate(url, sts.access_token.as_str()).await?,
+            None => (
+                sts.access_token,
+                current_time + chrono::Duration::seconds(sts.expires_in),
+            ),
+        };
+
+        let token = Token {
+            value: TokenValue::Bearer(access_token),
+            expiry,
+        };
+
+        let token_str = token.value.to_string();
+        self.current_token = Some(token);
+        Ok(token_str)
+    }
+}
+
+/// Encode and RS256-sign a JWT bearer assertion, as used by [`TokenManager`] to authenticate as
+/// a service account without ever sending its private key over the wire.
+fn sign_jwt(
+    header: &json::Value,
+    payload: &json::Value,
+    private_key_pem: &str,
+) -> Result<String, AuthError> {
+    let signing_input = format!(
+        "{}.{}",
+        base64_url(header.to_string().as_bytes()),
+        base64_url(payload.to_string().as_bytes()),
+    );
+
+    let signature = crate::crypto::sign(private_key_pem, signing_input.as_bytes())
+        .map_err(AuthError::Jwt)?;
+
+    Ok(format!("{}.{}", signing_input, base64_url(&signature)))
+}
+
+fn base64_url(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE_NO_PAD)
+}
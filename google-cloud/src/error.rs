@@ -0,0 +1,102 @@
+use std::fmt;
+
+/// Errors that can occur while acquiring or refreshing an access token.
+#[derive(Debug)]
+pub enum AuthError {
+    Io(std::io::Error),
+    Json(json::Error),
+    Hyper(hyper::Error),
+    Http(hyper::http::Error),
+    /// Signing or encoding the JWT bearer assertion used for service-account auth failed.
+    Jwt(String),
+    Config(String),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthError::Io(err) => write!(f, "io error: {}", err),
+            AuthError::Json(err) => write!(f, "json error: {}", err),
+            AuthError::Hyper(err) => write!(f, "hyper error: {}", err),
+            AuthError::Http(err) => write!(f, "http error: {}", err),
+            AuthError::Jwt(err) => write!(f, "jwt error: {}", err),
+            AuthError::Config(message) => write!(f, "configuration error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+impl From<std::io::Error> for AuthError {
+    fn from(err: std::io::Error) -> AuthError {
+        AuthError::Io(err)
+    }
+}
+
+impl From<json::Error> for AuthError {
+    fn from(err: json::Error) -> AuthError {
+        AuthError::Json(err)
+    }
+}
+
+impl From<hyper::Error> for AuthError {
+    fn from(err: hyper::Error) -> AuthError {
+        AuthError::Hyper(err)
+    }
+}
+
+impl From<hyper::http::Error> for AuthError {
+    fn from(err: hyper::http::Error) -> AuthError {
+        AuthError::Http(err)
+    }
+}
+
+/// The crate-wide error type.
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(json::Error),
+    Reqwest(reqwest::Error),
+    Auth(AuthError),
+    /// The server response didn't match what the client expected (e.g. a missing header or an
+    /// unrecognized status code).
+    Protocol(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "io error: {}", err),
+            Error::Json(err) => write!(f, "json error: {}", err),
+            Error::Reqwest(err) => write!(f, "reqwest error: {}", err),
+            Error::Auth(err) => write!(f, "auth error: {}", err),
+            Error::Protocol(message) => write!(f, "protocol error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<json::Error> for Error {
+    fn from(err: json::Error) -> Error {
+        Error::Json(err)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Error {
+        Error::Reqwest(err)
+    }
+}
+
+impl From<AuthError> for Error {
+    fn from(err: AuthError) -> Error {
+        Error::Auth(err)
+    }
+}
@@ -0,0 +1,10 @@
+//! Unofficial Rust client libraries for Google Cloud Platform services.
+
+pub mod authorize;
+pub(crate) mod crypto;
+pub mod error;
+
+#[cfg(feature = "storage")]
+pub mod storage;
+
+pub use crate::error::Error;
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketResource {
+    /// Value: "storage#bucket"
+    pub kind: String,
+    pub id: String,
+    pub self_link: String,
+    pub name: String,
+    pub project_number: String,
+    pub metageneration: String,
+    pub location: String,
+    pub storage_class: String,
+    pub etag: String,
+    pub time_created: String,
+    pub updated: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BucketResources {
+    /// Value: "storage#buckets"
+    pub kind: String,
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+    #[serde(default)]
+    pub items: Vec<BucketResource>,
+}
@@ -0,0 +1,4 @@
+pub(crate) mod bucket;
+pub(crate) mod bucket_acl;
+pub(crate) mod object;
+pub(crate) mod object_acl;
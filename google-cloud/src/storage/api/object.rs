@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectResource {
+    /// Value: "storage#object"
+    pub kind: String,
+    pub id: String,
+    pub self_link: String,
+    pub name: String,
+    pub bucket: String,
+    pub generation: String,
+    pub metageneration: String,
+    pub content_type: Option<String>,
+    pub storage_class: String,
+    pub size: String,
+    pub md5_hash: Option<String>,
+    pub media_link: String,
+    pub crc32c: Option<String>,
+    pub etag: String,
+    pub time_created: String,
+    pub updated: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectResources {
+    /// Value: "storage#objects"
+    pub kind: String,
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+    #[serde(default)]
+    pub items: Vec<ObjectResource>,
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+}
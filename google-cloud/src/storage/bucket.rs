@@ -1,6 +1,30 @@
-use crate::storage::api::object::ObjectResource;
+use bytes::{Bytes, BytesMut};
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use json::json;
+
+use crate::storage::api::object::{ObjectResource, ObjectResources};
 use crate::storage::{Client, Error, Object};
 
+/// Chunks are sent in multiples of 256 KiB, as required by the GCS resumable upload protocol.
+const CHUNK_SIZE: usize = 256 * 1024;
+
+/// How many of the `buffered` bytes accumulated so far to send as the next chunk, given `offset`
+/// bytes already committed out of the declared `size`. Errors if `buffered` already exceeds what
+/// remains, which means `data` yielded more bytes than `size` promised — sending a chunk that
+/// overruns `size` would make `put_chunk`'s `Content-Range` end exceed the declared total, which
+/// GCS rejects.
+fn next_chunk_take(buffered: usize, offset: u64, size: u64) -> Result<usize, Error> {
+    let remaining = size - offset;
+    if buffered as u64 > remaining {
+        return Err(Error::Protocol(format!(
+            "data stream yielded more than the declared size of {} bytes",
+            size,
+        )));
+    }
+
+    Ok(CHUNK_SIZE.min(buffered))
+}
+
 /// Represents a Cloud Storage bucket.
 #[derive(Clone)]
 pub struct Bucket {
@@ -64,6 +88,184 @@ impl Bucket {
         ))
     }
 
+    /// Insert a new object into the bucket via a resumable upload session, streaming `data`
+    /// instead of buffering it whole.
+    ///
+    /// `size` must be the total, known length of `data` in bytes. The payload is sent in
+    /// 256 KiB-aligned chunks; if a chunk upload fails, the session is queried for the last
+    /// byte it committed and the upload resumes from there.
+    pub async fn create_object_resumable(
+        &mut self,
+        name: &str,
+        mime_type: impl AsRef<str>,
+        mut data: impl Stream<Item = Bytes> + Unpin,
+        size: u64,
+    ) -> Result<Object, Error> {
+        let session_uri = self
+            .start_resumable_session(name, mime_type.as_ref())
+            .await?;
+
+        let mut offset = 0u64;
+        let mut buffer = BytesMut::new();
+
+        loop {
+            while buffer.len() < CHUNK_SIZE && offset + (buffer.len() as u64) < size {
+                match data.next().await {
+                    Some(chunk) => buffer.extend_from_slice(&chunk),
+                    None => {
+                        return Err(Error::Protocol(format!(
+                            "data stream ended after {} bytes, short of the declared size of {} bytes",
+                            offset + buffer.len() as u64,
+                            size,
+                        )));
+                    }
+                }
+            }
+
+            let take = next_chunk_take(buffer.len(), offset, size)?;
+            let chunk = buffer.split_to(take).freeze();
+
+            if let Some(resource) = self.put_chunk(&session_uri, chunk, offset, size).await? {
+                return Ok(Object::new(
+                    self.client.clone(),
+                    self.name.clone(),
+                    resource.name,
+                ));
+            }
+
+            offset += take as u64;
+        }
+    }
+
+    /// Start a resumable upload session and return the session URI handed back in the
+    /// `Location` header.
+    async fn start_resumable_session(
+        &mut self,
+        name: &str,
+        mime_type: &str,
+    ) -> Result<String, Error> {
+        let client = &mut self.client;
+        let inner = &client.client;
+        let uri = format!("{}/b/{}/o", Client::UPLOAD_ENDPOINT, self.name);
+
+        let body = json!({ "name": name });
+        let token = client.token_provider.lock().await.token().await?;
+        let request = inner
+            .post(uri.as_str())
+            .query(&[("uploadType", "resumable")])
+            .header("authorization", token)
+            .header("x-upload-content-type", mime_type)
+            .json(&body)
+            .send();
+        let response = request.await?.error_for_status()?;
+        let location = response
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| {
+                Error::Protocol("resumable upload response missing Location header".to_string())
+            })?
+            .to_string();
+
+        Ok(location)
+    }
+
+    /// PUT a single chunk of `[start, start + chunk.len())` out of `total` bytes, retrying once
+    /// by querying the session for its last committed byte if the chunk is rejected.
+    ///
+    /// Returns `Some(resource)` once the server reports the upload complete, `None` while more
+    /// chunks are expected.
+    async fn put_chunk(
+        &mut self,
+        session_uri: &str,
+        mut chunk: Bytes,
+        mut start: u64,
+        total: u64,
+    ) -> Result<Option<ObjectResource>, Error> {
+        const MAX_ATTEMPTS: u32 = 3;
+        let inner = self.client.client.clone();
+
+        for attempt in 0..MAX_ATTEMPTS {
+            let end = start + chunk.len() as u64;
+            let content_range = if chunk.is_empty() {
+                format!("bytes */{}", total)
+            } else {
+                format!("bytes {}-{}/{}", start, end - 1, total)
+            };
+
+            let response = inner
+                .put(session_uri)
+                .header("content-range", content_range)
+                .header("content-length", chunk.len())
+                .body(chunk.clone())
+                .send()
+                .await?;
+
+            match response.status().as_u16() {
+                200 | 201 => {
+                    let resource = response.json::<ObjectResource>().await?;
+                    return Ok(Some(resource));
+                }
+                308 => return Ok(None),
+                _ if attempt + 1 < MAX_ATTEMPTS => {
+                    let committed = self.query_resumable_offset(session_uri, total).await?;
+                    if committed > start {
+                        let skip = (committed - start).min(chunk.len() as u64) as usize;
+                        chunk = chunk.slice(skip..);
+                        start = committed;
+                    }
+                }
+                _ => {
+                    response.error_for_status()?;
+                    return Err(Error::Protocol(
+                        "resumable upload chunk was rejected".to_string(),
+                    ));
+                }
+            }
+        }
+
+        Err(Error::Protocol(
+            "resumable upload did not complete after retrying".to_string(),
+        ))
+    }
+
+    /// Query a resumable session for the last byte it has committed, as described in the GCS
+    /// resumable upload protocol (`PUT` with `Content-Range: bytes */{total}`).
+    async fn query_resumable_offset(&mut self, session_uri: &str, total: u64) -> Result<u64, Error> {
+        let inner = &self.client.client;
+        let response = inner
+            .put(session_uri)
+            .header("content-range", format!("bytes */{}", total))
+            .header("content-length", 0)
+            .send()
+            .await?;
+
+        if response.status().as_u16() != 308 {
+            return Ok(total);
+        }
+
+        let committed = response
+            .headers()
+            .get(reqwest::header::RANGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|range| range.rsplit('-').next())
+            .and_then(|last| last.parse::<u64>().ok())
+            .map(|last| last + 1)
+            .unwrap_or(0);
+
+        Ok(committed)
+    }
+
+    /// Start building a listing of the objects in this bucket.
+    pub fn list_objects(&self) -> ListObjectsBuilder {
+        ListObjectsBuilder {
+            bucket: self.clone(),
+            prefix: None,
+            delimiter: None,
+            max_results: None,
+        }
+    }
+
     /// Get an object stored in the bucket.
     pub async fn object(&mut self, name: &str) -> Result<Object, Error> {
         let client = &mut self.client;
@@ -103,3 +305,164 @@ impl Bucket {
         Ok(())
     }
 }
+
+/// Builds an object listing request for [`Bucket::list_objects`].
+pub struct ListObjectsBuilder {
+    bucket: Bucket,
+    prefix: Option<String>,
+    delimiter: Option<String>,
+    max_results: Option<u32>,
+}
+
+impl ListObjectsBuilder {
+    /// Only list objects whose name starts with `prefix`.
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Group names past `delimiter` into the `prefixes` returned by [`ListObjectsBuilder::list`],
+    /// enabling directory-style traversal.
+    pub fn delimiter(mut self, delimiter: impl Into<String>) -> Self {
+        self.delimiter = Some(delimiter.into());
+        self
+    }
+
+    /// Cap the number of objects returned per page.
+    pub fn max_results(mut self, max_results: u32) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Stream every matching object, transparently following `nextPageToken` until the
+    /// listing is exhausted.
+    pub fn stream(self) -> BoxStream<'static, Result<Object, Error>> {
+        let state = ListObjectsState {
+            builder: self,
+            page_token: None,
+            buffer: Vec::new().into_iter(),
+            exhausted: false,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(resource) = state.buffer.next() {
+                    let object = Object::new(
+                        state.builder.bucket.client.clone(),
+                        state.builder.bucket.name.clone(),
+                        resource.name,
+                    );
+                    return Some((Ok(object), state));
+                }
+
+                if state.exhausted {
+                    return None;
+                }
+
+                match state.builder.fetch_page(state.page_token.take()).await {
+                    Ok(page) => {
+                        state.exhausted = page.next_page_token.is_none();
+                        state.page_token = page.next_page_token;
+                        state.buffer = page.items.into_iter();
+                    }
+                    Err(err) => {
+                        state.exhausted = true;
+                        return Some((Err(err), state));
+                    }
+                }
+            }
+        })
+        .boxed()
+    }
+
+    /// Collect every matching object, along with the common prefixes ("directories") returned
+    /// when [`ListObjectsBuilder::delimiter`] was set.
+    pub async fn list(&self) -> Result<(Vec<Object>, Vec<String>), Error> {
+        let mut objects = Vec::new();
+        let mut prefixes = Vec::new();
+        let mut page_token = None;
+
+        loop {
+            let page = self.fetch_page(page_token.take()).await?;
+            objects.extend(page.items.into_iter().map(|resource| {
+                Object::new(
+                    self.bucket.client.clone(),
+                    self.bucket.name.clone(),
+                    resource.name,
+                )
+            }));
+            prefixes.extend(page.prefixes);
+
+            match page.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+
+        Ok((objects, prefixes))
+    }
+
+    async fn fetch_page(&self, page_token: Option<String>) -> Result<ObjectResources, Error> {
+        let client = &self.bucket.client;
+        let inner = &client.client;
+        let uri = format!("{}/b/{}/o", Client::ENDPOINT, self.bucket.name);
+
+        let mut query_pairs = Vec::new();
+        if let Some(ref prefix) = self.prefix {
+            query_pairs.push(("prefix".to_string(), prefix.clone()));
+        }
+        if let Some(ref delimiter) = self.delimiter {
+            query_pairs.push(("delimiter".to_string(), delimiter.clone()));
+        }
+        if let Some(max_results) = self.max_results {
+            query_pairs.push(("maxResults".to_string(), max_results.to_string()));
+        }
+        if let Some(page_token) = page_token {
+            query_pairs.push(("pageToken".to_string(), page_token));
+        }
+
+        let token = client.token_provider.lock().await.token().await?;
+        let request = inner
+            .get(uri.as_str())
+            .query(query_pairs.as_slice())
+            .header("authorization", token)
+            .send();
+        let response = request.await?;
+        let resources = response.error_for_status()?.json::<ObjectResources>().await?;
+
+        Ok(resources)
+    }
+}
+
+struct ListObjectsState {
+    builder: ListObjectsBuilder,
+    page_token: Option<String>,
+    buffer: std::vec::IntoIter<ObjectResource>,
+    exhausted: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_chunk_take_fills_up_to_chunk_size() {
+        assert_eq!(next_chunk_take(100, 0, 1_000_000).unwrap(), 100);
+        assert_eq!(
+            next_chunk_take(CHUNK_SIZE + 10, 0, 1_000_000).unwrap(),
+            CHUNK_SIZE
+        );
+    }
+
+    #[test]
+    fn next_chunk_take_caps_the_final_chunk_at_the_declared_size() {
+        // Exactly the 100 remaining bytes are buffered; that's the final chunk.
+        assert_eq!(next_chunk_take(100, 900, 1_000).unwrap(), 100);
+    }
+
+    #[test]
+    fn next_chunk_take_errors_when_the_stream_overshoots_the_declared_size() {
+        // The stream handed back more bytes than `size` said would ever exist.
+        assert!(next_chunk_take(1_000, 0, 100).is_err());
+    }
+}
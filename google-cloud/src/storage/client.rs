@@ -1,11 +1,15 @@
 use std::env;
 use std::fs::File;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use json::json;
 use tokio::sync::Mutex;
 
-use crate::authorize::{ApplicationCredentials, MetadataManager, TokenProvider, TokenManager};
+use crate::authorize::{
+    ApplicationCredentials, AuthorizedUserCredentials, ExternalAccountCredentials,
+    ExternalAccountManager, MetadataManager, TokenManager, TokenProvider, UserTokenManager,
+};
 use crate::storage::api::bucket::{BucketResource, BucketResources};
 use crate::storage::{Bucket, Object, Error};
 
@@ -15,10 +19,13 @@ pub struct Client {
     pub(crate) project_name: String,
     pub(crate) client: Arc<reqwest::Client>,
     pub(crate) token_provider: Arc<Mutex<Box<dyn TokenProvider + Send>>>,
+    /// The service-account credentials backing this client, if any, used to sign V4 URLs.
+    /// Clients built from the metadata server or an `authorized_user` refresh token don't hold
+    /// a private key and so can't sign URLs.
+    pub(crate) signer: Option<Arc<ApplicationCredentials>>,
 }
 
 impl Client {
-    #[allow(unused)]
     pub(crate) const DOMAIN_NAME: &'static str = "storage.googleapis.com";
     pub(crate) const ENDPOINT: &'static str = "https://storage.googleapis.com/storage/v1";
     /// Cloud Storage uses a slightly different endpoint for uploads.
@@ -37,28 +44,112 @@ impl Client {
         }
     }
 
+    /// Build the `reqwest::Client` shared by every credential path.
+    ///
+    /// With the `rustls-tls` feature, this pins the client to the bundled root store instead of
+    /// the platform's, so the crate builds and runs cleanly on musl/static targets without
+    /// OpenSSL. Otherwise the platform's native TLS stack is used.
+    fn http_client() -> Result<reqwest::Client, Error> {
+        let builder = reqwest::Client::builder();
+
+        #[cfg(feature = "rustls-tls")]
+        let builder = builder.use_rustls_tls();
+
+        Ok(builder.build()?)
+    }
+
     /// Create a new client for the specified project.
     ///
-    /// Credentials are looked up in the `GOOGLE_APPLICATION_CREDENTIALS` environment variable.
+    /// Credentials are resolved using the standard Application Default Credentials order:
+    /// the `GOOGLE_APPLICATION_CREDENTIALS` environment variable, then the well-known file
+    /// written by `gcloud auth application-default login`, then the GCE/Cloud Run metadata
+    /// server.
     pub async fn new(project_name: impl Into<String>) -> Result<Client, Error> {
         if let Ok(path) = env::var("GOOGLE_APPLICATION_CREDENTIALS") {
-			let file = File::open(path)?;
-			let creds = json::from_reader(file)?;
-			Client::from_credentials(project_name, creds).await
-		} else if let Ok(_) = std::env::var("K_SERVICE") {
-			let client = reqwest::Client::builder()
-				.build()?;
-
-			Ok(Client {
-				client: Arc::new(client),
-				project_name: project_name.into(),
-				token_provider: Arc::new(Mutex::new(Box::new(MetadataManager::new(
-					Client::SCOPES.as_ref(),
-				)) as Box<dyn TokenProvider + Send>)),
-			})
-		} else {
-			Err(Error::Auth(crate::error::AuthError::Config("Missing both GOOGLE_APPLICATION_CREDENTIALS and metadata service.".to_string())))
-		}
+            Client::from_credentials_file(project_name, path).await
+        } else if let Some(path) = Client::well_known_credentials_path().filter(|p| p.exists()) {
+            Client::from_credentials_file(project_name, path).await
+        } else if std::env::var("K_SERVICE").is_ok() {
+            let client = Client::http_client()?;
+
+            Ok(Client {
+                client: Arc::new(client),
+                project_name: project_name.into(),
+                token_provider: Arc::new(Mutex::new(Box::new(MetadataManager::new(
+                    Client::SCOPES.as_ref(),
+                )) as Box<dyn TokenProvider + Send>)),
+                signer: None,
+            })
+        } else {
+            Err(Error::Auth(crate::error::AuthError::Config(
+                "Missing GOOGLE_APPLICATION_CREDENTIALS, the well-known gcloud credentials file, and the metadata service.".to_string(),
+            )))
+        }
+    }
+
+    /// The path of the `application_default_credentials.json` file written by
+    /// `gcloud auth application-default login`.
+    fn well_known_credentials_path() -> Option<PathBuf> {
+        if cfg!(windows) {
+            env::var_os("APPDATA").map(|appdata| {
+                PathBuf::from(appdata)
+                    .join("gcloud")
+                    .join("application_default_credentials.json")
+            })
+        } else {
+            env::var_os("HOME").map(|home| {
+                PathBuf::from(home)
+                    .join(".config")
+                    .join("gcloud")
+                    .join("application_default_credentials.json")
+            })
+        }
+    }
+
+    /// Load a credentials file and build a client from it, dispatching on its `"type"` field:
+    /// a service-account key uses [`Client::from_credentials`], an `authorized_user` file (as
+    /// written by `gcloud auth application-default login`) refreshes an existing OAuth2
+    /// refresh token, and an `external_account` file exchanges a federated identity token via
+    /// Workload Identity Federation.
+    async fn from_credentials_file(
+        project_name: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> Result<Client, Error> {
+        let file = File::open(path)?;
+        let value: json::Value = json::from_reader(file)?;
+
+        match value.get("type").and_then(|t| t.as_str()) {
+            Some("authorized_user") => {
+                let creds: AuthorizedUserCredentials = json::from_value(value)?;
+                let client = Client::http_client()?;
+
+                Ok(Client {
+                    client: Arc::new(client),
+                    project_name: project_name.into(),
+                    token_provider: Arc::new(Mutex::new(Box::new(UserTokenManager::new(creds))
+                        as Box<dyn TokenProvider + Send>)),
+                    signer: None,
+                })
+            }
+            Some("external_account") => {
+                let creds: ExternalAccountCredentials = json::from_value(value)?;
+                let client = Client::http_client()?;
+
+                Ok(Client {
+                    client: Arc::new(client),
+                    project_name: project_name.into(),
+                    token_provider: Arc::new(Mutex::new(Box::new(ExternalAccountManager::new(
+                        creds,
+                        Client::SCOPES.as_ref(),
+                    )) as Box<dyn TokenProvider + Send>)),
+                    signer: None,
+                })
+            }
+            _ => {
+                let creds: ApplicationCredentials = json::from_value(value)?;
+                Client::from_credentials(project_name, creds).await
+            }
+        }
     }
 
     /// Create a new client for the specified project with custom credentials.
@@ -66,10 +157,8 @@ impl Client {
         project_name: impl Into<String>,
         creds: ApplicationCredentials,
     ) -> Result<Client, Error> {
-        // let certificate = reqwest::Certificate::from_pem(TLS_CERTS)?;
-        let client = reqwest::Client::builder()
-            // .add_root_certificate(certificate)
-            .build()?;
+        let client = Client::http_client()?;
+        let signer = Arc::new(creds.clone());
 
         Ok(Client {
             client: Arc::new(client),
@@ -78,6 +167,7 @@ impl Client {
                 creds,
                 Client::SCOPES.as_ref(),
             )) as Box<dyn TokenProvider + Send>)),
+            signer: Some(signer),
         })
     }
 
@@ -105,28 +195,45 @@ impl Client {
         Object::new(self.clone(), bucket, name)
     }
 
-    /// List all existing buckets of the current project.
+    /// List all existing buckets of the current project, following `nextPageToken` across
+    /// pages until exhausted.
     pub async fn buckets(&mut self) -> Result<Vec<Bucket>, Error> {
         let inner = &self.client;
         let uri = format!("{}/b", Client::ENDPOINT);
 
-        let token = self.token_provider.lock().await.token().await?;
-        let request = inner
-            .get(uri.as_str())
-            .query(&[("project", self.project_name.as_str())])
-            .header("authorization", token)
-            .send();
-        let response = request.await?;
-        let resources = response
-            .error_for_status()?
-            .json::<BucketResources>()
-            .await?;
+        let mut buckets = Vec::new();
+        let mut page_token: Option<String> = None;
 
-        let buckets = resources
-            .items
-            .into_iter()
-            .map(|resource| Bucket::new(self.clone(), resource.name))
-            .collect();
+        loop {
+            let mut query_pairs = vec![("project".to_string(), self.project_name.clone())];
+            if let Some(page_token) = page_token.take() {
+                query_pairs.push(("pageToken".to_string(), page_token));
+            }
+
+            let token = self.token_provider.lock().await.token().await?;
+            let request = inner
+                .get(uri.as_str())
+                .query(query_pairs.as_slice())
+                .header("authorization", token)
+                .send();
+            let response = request.await?;
+            let resources = response
+                .error_for_status()?
+                .json::<BucketResources>()
+                .await?;
+
+            buckets.extend(
+                resources
+                    .items
+                    .into_iter()
+                    .map(|resource| Bucket::new(self.clone(), resource.name)),
+            );
+
+            match resources.next_page_token {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
 
         Ok(buckets)
     }
@@ -0,0 +1,11 @@
+//! Cloud Storage client.
+
+pub(crate) mod api;
+mod bucket;
+mod client;
+mod object;
+
+pub use crate::error::Error;
+pub use bucket::Bucket;
+pub use client::Client;
+pub use object::Object;